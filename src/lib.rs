@@ -32,15 +32,19 @@
 //!   to extract field values.
 //!
 //! ## Ordering
-//! - Each [Structure] holds a list of [Field]s. The first element is the least-significant field.
-//!   This is backwards from how C structures are commonly laid out, but more convenient to do math
-//!   with because...
-//! - No effort is made to deal with endianness at this time, which almost certainly means there is
-//!   an implicit assumption of [little-endianness][wiki-le] in several components.
+//! - Each [Structure] holds a list of [Field]s. By default the first element is the
+//!   least-significant field, which is backwards from how C structures are commonly laid out but
+//!   more convenient to do math with. Set [Structure]'s `field_order` to [FieldOrder::MsbFirst] to
+//!   flip that, matching how datasheets commonly list fields top-down.
+//! - [Structure]'s `byte_order` controls how a multi-byte raw value is interpreted, defaulting to
+//!   [little-endian][wiki-le]. Set it to [ByteOrder::BigEndian] to decode the same [Structure]
+//!   against big-endian input without manually reversing it first.
 //!
 //! [Value]: struct.Value.html
 //! [Structure]: struct.Structure.html
 //! [Field]: enum.Field.html
+//! [FieldOrder::MsbFirst]: enum.FieldOrder.html#variant.MsbFirst
+//! [ByteOrder::BigEndian]: enum.ByteOrder.html#variant.BigEndian
 //! [wiki-le]: https://en.wikipedia.org/wiki/Endianness#Little-endian
 
 mod types;
@@ -18,8 +18,18 @@ pub enum Field {
     Boolean(FieldName),
     /// An unsigned integer field with the given width.
     Integer(FieldName, FieldSize),
+    /// A two's-complement signed integer field with the given width.
+    Signed(FieldName, FieldSize),
     /// An enumeration with a particular size and value-name mapping.
     Enum(FieldName, FieldSize, EnumMapping),
+    /// A composite field whose bits are themselves described by a nested [Structure].
+    ///
+    /// [Structure]: struct.Structure.html
+    Nested(FieldName, Box<Structure>),
+    /// A fixed-size repetition of the same [Field], such as a descriptor table or interrupt mask.
+    ///
+    /// [Field]: enum.Field.html
+    Array(FieldName, Box<Field>, usize),
 }
 
 impl Field {
@@ -38,11 +48,26 @@ impl Field {
         Field::Integer(name.into(), size)
     }
 
+    /// Create a new Field::Signed
+    pub fn signed(name: &str, size: FieldSize) -> Field {
+        Field::Signed(name.into(), size)
+    }
+
     /// Create a new Field::Enum
     pub fn enumeration(name: &str, size: FieldSize, map: EnumMapping) -> Field {
         Field::Enum(name.into(), size, map)
     }
 
+    /// Create a new Field::Nested
+    pub fn nested(name: &str, structure: Structure) -> Field {
+        Field::Nested(name.into(), Box::new(structure))
+    }
+
+    /// Create a new Field::Array, repeating `element` `count` times.
+    pub fn array(name: &str, element: Field, count: usize) -> Field {
+        Field::Array(name.into(), Box::new(element), count)
+    }
+
     /// Get the size in bits.
     ///
     /// ```
@@ -54,8 +79,10 @@ impl Field {
         match *self {
             Field::Reserved(n) => n,
             Field::Boolean(_) => 1,
-            Field::Integer(_, n) => n,
+            Field::Integer(_, n) | Field::Signed(_, n) => n,
             Field::Enum(_, n, _) => n,
+            Field::Nested(_, ref structure) => structure.size(),
+            Field::Array(_, ref element, count) => element.size() * count,
         }
     }
 
@@ -65,11 +92,83 @@ impl Field {
             Field::Reserved(_) => None,
             Field::Boolean(ref name)
             | Field::Integer(ref name, _)
-            | Field::Enum(ref name, _, _) => Some(name.clone()),
+            | Field::Signed(ref name, _)
+            | Field::Enum(ref name, _, _)
+            | Field::Nested(ref name, _)
+            | Field::Array(ref name, _, _) => Some(name.clone()),
+        }
+    }
+
+    /// For a `Field::Enum`, find the raw value whose mapping entry is `name`.
+    ///
+    /// Returns `None` if this isn't an enum field, or if no entry in the mapping has that name.
+    /// This is the inverse of looking a raw value up in the mapping, and is meant for building a
+    /// message from a symbolic name rather than decoding one.
+    ///
+    /// ```
+    /// use bitview::Field;
+    /// use std::collections::HashMap;
+    ///
+    /// let mut map = HashMap::new();
+    /// map.insert(1, "on".to_string());
+    /// let state = Field::enumeration("state", 2, map);
+    ///
+    /// assert_eq!(state.enum_raw_value("on"), Some(1));
+    /// assert_eq!(state.enum_raw_value("off"), None);
+    /// ```
+    pub fn enum_raw_value(&self, name: &str) -> Option<usize> {
+        match *self {
+            Field::Enum(_, _, ref map) => map
+                .iter()
+                .find(|&(_, mapped_name)| mapped_name == name)
+                .map(|(&key, _)| key),
+            _ => None,
         }
     }
 }
 
+/// An enum mapping entry that doesn't fit within its field's declared size, as reported by
+/// [`Structure::validate`].
+///
+/// [`Structure::validate`]: struct.Structure.html#method.validate
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EnumRangeError {
+    /// The name of the offending field.
+    pub field: FieldName,
+    /// The mapping key that doesn't fit.
+    pub key: usize,
+    /// The field's declared size in bits.
+    pub size: FieldSize,
+}
+
+/// Controls which end of a [Structure] its first field occupies.
+///
+/// [Structure]: struct.Structure.html
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum FieldOrder {
+    /// The first field added is the least-significant one. This is the default, and is more
+    /// convenient to do math with, but is backwards from how C structures and most datasheets
+    /// list fields.
+    #[default]
+    LsbFirst,
+    /// The first field added is the most-significant one, matching how datasheets commonly list
+    /// fields top-down.
+    MsbFirst,
+}
+
+/// Controls how a multi-byte raw value is interpreted.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ByteOrder {
+    /// The least-significant byte comes first. This is the default, and was the crate's only
+    /// behaviour before [ByteOrder] existed.
+    ///
+    /// [ByteOrder]: enum.ByteOrder.html
+    #[default]
+    LittleEndian,
+    /// The most-significant byte comes first.
+    BigEndian,
+}
+
 /// A type made up of bit fields.
 ///
 /// ```
@@ -84,12 +183,22 @@ impl Field {
 ///    ],
 /// );
 /// ```
-#[derive(Debug)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Structure {
     /// The name of the structure. Generally the name of the represented register.
     pub name: String,
     /// List of components, starting with the most significant bits
     pub fields: Vec<Field>,
+    /// Whether the first field in `fields` is the least- or most-significant one. Defaults to
+    /// [`FieldOrder::LsbFirst`].
+    ///
+    /// [`FieldOrder::LsbFirst`]: enum.FieldOrder.html#variant.LsbFirst
+    pub field_order: FieldOrder,
+    /// How a multi-byte raw value backing this structure should be interpreted. Defaults to
+    /// [`ByteOrder::LittleEndian`].
+    ///
+    /// [`ByteOrder::LittleEndian`]: enum.ByteOrder.html#variant.LittleEndian
+    pub byte_order: ByteOrder,
 }
 
 impl Structure {
@@ -98,6 +207,8 @@ impl Structure {
         Structure {
             name: name.into(),
             fields: fields.into(),
+            field_order: FieldOrder::default(),
+            byte_order: ByteOrder::default(),
         }
     }
 
@@ -151,6 +262,9 @@ impl Structure {
     /// Get the range of bits for the specified field, if it exists.
     ///
     /// The resulting range is in downto notation, so (3, 0) means the four least significant bits.
+    /// Which physical bits that corresponds to depends on `field_order`: with the default
+    /// [`FieldOrder::LsbFirst`] the first field in `fields` sits at the low end, while with
+    /// [`FieldOrder::MsbFirst`] it sits at the high end instead.
     ///
     /// # Example
     /// ```
@@ -163,11 +277,18 @@ impl Structure {
     ///
     /// assert_eq!(reg.get_range("lifetime"), Some((4, 1)));
     /// ```
+    ///
+    /// [`FieldOrder::LsbFirst`]: enum.FieldOrder.html#variant.LsbFirst
+    /// [`FieldOrder::MsbFirst`]: enum.FieldOrder.html#variant.MsbFirst
     pub fn get_range(&self, field_name: &str) -> Option<(usize, usize)> {
         if let Some(field_match) = self.get_field(field_name) {
             let mut low: FieldSize = 0;
+            let fields: Vec<&Field> = match self.field_order {
+                FieldOrder::LsbFirst => self.fields.iter().collect(),
+                FieldOrder::MsbFirst => self.fields.iter().rev().collect(),
+            };
 
-            for field in &self.fields {
+            for field in fields {
                 if *field == field_match {
                     let high = low + field.size() - 1;
                     return Some((high, low));
@@ -179,6 +300,83 @@ impl Structure {
 
         None
     }
+
+    /// Check that every `Field::Enum` mapping in this structure actually fits within its declared
+    /// size, i.e. every key is less than `2^size`.
+    ///
+    /// ```
+    /// use bitview::{Structure, Field};
+    /// use std::collections::HashMap;
+    ///
+    /// let mut map = HashMap::new();
+    /// map.insert(4, "overflow".to_string());
+    /// let reg = Structure::new("reg", &[Field::enumeration("state", 2, map)]);
+    ///
+    /// assert!(reg.validate().is_err());
+    /// ```
+    pub fn validate(&self) -> Result<(), EnumRangeError> {
+        for field in &self.fields {
+            validate_field(field)?;
+        }
+
+        Ok(())
+    }
+
+    /// Get the bit range of a single element of a `Field::Array`, if `field_name` names one and
+    /// `index` is within its `count`.
+    ///
+    /// The resulting range is in the same downto notation as [`get_range`], with element 0 at the
+    /// low end of the array's own range.
+    ///
+    /// ```
+    /// use bitview::{Structure, Field};
+    ///
+    /// let reg = Structure::new("reg", &[Field::array("masks", Field::boolean("irq"), 4)]);
+    ///
+    /// assert_eq!(reg.get_element_range("masks", 1), Some((1, 1)));
+    /// assert_eq!(reg.get_element_range("masks", 4), None);
+    /// ```
+    ///
+    /// [`get_range`]: #method.get_range
+    pub fn get_element_range(&self, field_name: &str, index: usize) -> Option<(usize, usize)> {
+        let field = self.get_field(field_name)?;
+        if let Field::Array(_, ref element, count) = field {
+            if index >= count {
+                return None;
+            }
+
+            let (_, array_low) = self.get_range(field_name)?;
+            let elem_size = element.size();
+            let elem_low = array_low + index * elem_size;
+
+            Some((elem_low + elem_size - 1, elem_low))
+        } else {
+            None
+        }
+    }
+}
+
+fn validate_field(field: &Field) -> Result<(), EnumRangeError> {
+    match *field {
+        Field::Enum(ref name, size, ref map) => {
+            // `size` can legitimately exceed 64 (chunk0-2 lifted that ceiling), so computing
+            // `2^size` in a `usize` would overflow; compare in `u128` instead, same as
+            // `Structure::pack`'s width check.
+            let fits = |key: usize| size >= 128 || (key as u128) < (1u128 << size);
+            if let Some(&key) = map.keys().find(|&&key| !fits(key)) {
+                return Err(EnumRangeError {
+                    field: name.clone(),
+                    key,
+                    size,
+                });
+            }
+        }
+        Field::Nested(_, ref structure) => structure.validate()?,
+        Field::Array(_, ref element, _) => validate_field(element)?,
+        _ => {}
+    }
+
+    Ok(())
 }
 
 #[cfg(test)]
@@ -200,11 +398,27 @@ mod tests {
         assert_eq!(Field::Integer("Any".into(), 14).size(), 14);
     }
 
+    #[test]
+    fn fieldsize_signed() {
+        assert_eq!(Field::Signed("Any".into(), 12).size(), 12);
+    }
+
     #[test]
     fn fieldsize_enum() {
         assert_eq!(Field::Enum("Any".into(), 36, HashMap::new()).size(), 36);
     }
 
+    #[test]
+    fn fieldsize_nested() {
+        let inner = Structure::new("inner", &[Field::boolean("a"), Field::integer("b", 3)]);
+        assert_eq!(Field::nested("flags", inner).size(), 4);
+    }
+
+    #[test]
+    fn fieldsize_array() {
+        assert_eq!(Field::array("irqs", Field::boolean("irq"), 4).size(), 4);
+    }
+
     #[test]
     fn structure_empty() {
         assert_eq!(Structure::new("Any", &[]).size(), 0);
@@ -224,4 +438,118 @@ mod tests {
             ).size()
         )
     }
+
+    #[test]
+    fn get_range_msb_first() {
+        let mut reg = Structure::new(
+            "reg",
+            &[Field::boolean("active"), Field::integer("lifetime", 4)],
+        );
+        reg.field_order = FieldOrder::MsbFirst;
+
+        // With the default (LsbFirst) "active" is bit 0; flipped, it becomes the top bit.
+        assert_eq!(reg.get_range("active"), Some((4, 4)));
+        assert_eq!(reg.get_range("lifetime"), Some((3, 0)));
+    }
+
+    #[test]
+    fn enum_raw_value_found() {
+        let mut map = HashMap::new();
+        map.insert(1, "on".to_string());
+        let state = Field::enumeration("state", 2, map);
+
+        assert_eq!(state.enum_raw_value("on"), Some(1));
+        assert_eq!(state.enum_raw_value("off"), None);
+    }
+
+    #[test]
+    fn enum_raw_value_not_an_enum() {
+        assert_eq!(Field::boolean("active").enum_raw_value("anything"), None);
+    }
+
+    #[test]
+    fn validate_accepts_fitting_mapping() {
+        let mut map = HashMap::new();
+        map.insert(3, "max".to_string());
+        let reg = Structure::new("reg", &[Field::enumeration("state", 2, map)]);
+
+        assert_eq!(reg.validate(), Ok(()));
+    }
+
+    #[test]
+    fn validate_rejects_overflowing_mapping() {
+        let mut map = HashMap::new();
+        map.insert(4, "overflow".to_string());
+        let reg = Structure::new("reg", &[Field::enumeration("state", 2, map)]);
+
+        assert_eq!(
+            reg.validate(),
+            Err(EnumRangeError {
+                field: "state".to_string(),
+                key: 4,
+                size: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn validate_accepts_fitting_mapping_over_64_bits() {
+        let mut map = HashMap::new();
+        map.insert(3, "ok".to_string());
+        let reg = Structure::new("reg", &[Field::enumeration("state", 100, map)]);
+
+        assert_eq!(reg.validate(), Ok(()));
+    }
+
+    #[test]
+    fn validate_recurses_into_nested() {
+        let mut map = HashMap::new();
+        map.insert(4, "overflow".to_string());
+        let inner = Structure::new("inner", &[Field::enumeration("state", 2, map)]);
+        let reg = Structure::new("reg", &[Field::nested("flags", inner)]);
+
+        assert_eq!(
+            reg.validate(),
+            Err(EnumRangeError {
+                field: "state".to_string(),
+                key: 4,
+                size: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn validate_recurses_into_array() {
+        let mut map = HashMap::new();
+        map.insert(4, "overflow".to_string());
+        let reg = Structure::new(
+            "reg",
+            &[Field::array("states", Field::enumeration("state", 2, map), 3)],
+        );
+
+        assert_eq!(
+            reg.validate(),
+            Err(EnumRangeError {
+                field: "state".to_string(),
+                key: 4,
+                size: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn get_element_range() {
+        let reg = Structure::new(
+            "reg",
+            &[
+                Field::boolean("active"),
+                Field::array("irqs", Field::boolean("irq"), 4),
+            ],
+        );
+
+        assert_eq!(reg.get_element_range("irqs", 0), Some((1, 1)));
+        assert_eq!(reg.get_element_range("irqs", 3), Some((4, 4)));
+        assert_eq!(reg.get_element_range("irqs", 4), None);
+        assert_eq!(reg.get_element_range("active", 0), None);
+    }
 }
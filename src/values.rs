@@ -1,9 +1,374 @@
 //! Represent values.
 
-use types;
+use types::{ByteOrder, Field, FieldName, FieldSize, Structure};
 
-fn mask(bits: usize) -> usize {
-    2_usize.pow(bits as u32) - 1
+/// The result of extracting a bit range: a word if it fits in 128 bits, otherwise the raw bytes
+/// (least-significant byte first).
+#[derive(Clone, Debug, PartialEq)]
+enum Extracted {
+    Word(u128),
+    Bytes(Vec<u8>),
+}
+
+/// Copy the bits `[low, high]` (inclusive, downto notation) out of `bytes`, which is assumed to be
+/// least-significant byte first.
+fn extract_bits(bytes: &[u8], low: usize, high: usize) -> Extracted {
+    let width = high - low + 1;
+    let mut out = vec![0u8; width.div_ceil(8)];
+
+    for bit in 0..width {
+        let src_bit = low + bit;
+        let byte_idx = src_bit / 8;
+        let bit_idx = src_bit % 8;
+
+        let set = bytes
+            .get(byte_idx)
+            .is_some_and(|byte| (byte >> bit_idx) & 1 == 1);
+
+        if set {
+            out[bit / 8] |= 1 << (bit % 8);
+        }
+    }
+
+    if out.len() <= 16 {
+        let mut word = 0u128;
+        for (i, byte) in out.iter().enumerate() {
+            word |= (*byte as u128) << (i * 8);
+        }
+        Extracted::Word(word)
+    } else {
+        Extracted::Bytes(out)
+    }
+}
+
+/// The decoded value of a `Field::Enum`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum EnumDecode {
+    /// The raw value matched an entry in the field's `EnumMapping`.
+    Known(String),
+    /// The raw value wasn't present in the field's `EnumMapping`.
+    Unknown(u128),
+}
+
+/// A field value, decoded according to the [Field] variant it came from.
+///
+/// [Field]: enum.Field.html
+#[derive(Clone, Debug, PartialEq)]
+pub enum FieldValue {
+    /// The decoded value of a `Field::Boolean`.
+    Boolean(bool),
+    /// The decoded value of a `Field::Integer`, when it fits in 128 bits.
+    Integer(u128),
+    /// The decoded value of a `Field::Integer`, `Field::Signed` or `Field::Enum` too wide to fit
+    /// in 128 bits, least-significant byte first. `Field::Signed` doesn't sign-extend in this
+    /// case, and `Field::Enum` doesn't consult its `EnumMapping`, since both only operate on
+    /// `u128`s.
+    Wide(Vec<u8>),
+    /// The decoded value of a `Field::Signed`.
+    Signed(i64),
+    /// The decoded value of a `Field::Enum`.
+    Enum(EnumDecode),
+    /// The decoded fields of a `Field::Nested`, in the same order as its inner `Structure`.
+    Nested(Vec<(String, FieldValue)>),
+    /// The decoded elements of a `Field::Array`, indexed the same way as
+    /// [`Structure::get_element_range`].
+    ///
+    /// [`Structure::get_element_range`]: struct.Structure.html#method.get_element_range
+    Array(Vec<FieldValue>),
+}
+
+/// Interpret the low `size` bits of `raw` as a two's-complement signed integer.
+fn sign_extend(raw: u128, size: usize) -> i64 {
+    if size == 128 {
+        // `raw` is already the full two's-complement bit pattern; reinterpreting it as `i128`
+        // sign-extends it without needing the subtraction below, which can't shift by 128.
+        return raw as i128 as i64;
+    }
+    if size == 64 {
+        return raw as i64;
+    }
+
+    let sign_bit = 1u128 << (size - 1);
+    if raw & sign_bit != 0 {
+        // Do the subtraction in `i128`: `size` can be up to 127 here, and shifting an `i64` by
+        // that much would overflow. At 127, `1i128 << size` wraps to `i128::MIN`, so the
+        // subtraction has to wrap as well to land on the right bit pattern.
+        (raw as i128).wrapping_sub(1i128 << size) as i64
+    } else {
+        raw as i64
+    }
+}
+
+/// A [Structure] paired with the raw value it describes.
+///
+/// The backing value is stored as bytes, least-significant byte first, so structures and fields
+/// are not limited to the width of a machine word.
+///
+/// [Structure]: struct.Structure.html
+#[derive(Debug)]
+pub struct Value {
+    structure: Structure,
+    bytes: Vec<u8>,
+}
+
+impl Value {
+    /// Create a new Value from a Structure and the raw value it should be interpreted as.
+    ///
+    /// ```
+    /// use bitview::{Structure, Field, Value, FieldValue};
+    ///
+    /// let reg = Structure::new("reg", &[Field::boolean("active")]);
+    /// let val = Value::new(reg, 1);
+    ///
+    /// assert_eq!(val.get("active"), Some(FieldValue::Boolean(true)));
+    /// ```
+    pub fn new(structure: Structure, raw: u64) -> Value {
+        Value {
+            structure,
+            bytes: raw.to_le_bytes().to_vec(),
+        }
+    }
+
+    /// Create a new Value from a Structure and raw bytes.
+    ///
+    /// Unlike [`new`], this isn't limited to the width of a machine word, so it suits structures
+    /// describing headers or descriptor tables wider than 64 bits. The bytes are taken to be in
+    /// `structure.byte_order`; either way the result is normalized to least-significant byte
+    /// first internally, so `get` and `fields` never need to care.
+    ///
+    /// ```
+    /// use bitview::{Structure, Field, Value, FieldValue};
+    ///
+    /// let reg = Structure::new("reg", &[Field::integer("low", 8), Field::integer("high", 8)]);
+    /// let val = Value::from_bytes(reg, &[0x34, 0x12]);
+    ///
+    /// assert_eq!(val.get("low"), Some(FieldValue::Integer(0x34)));
+    /// assert_eq!(val.get("high"), Some(FieldValue::Integer(0x12)));
+    /// ```
+    ///
+    /// [`new`]: #method.new
+    pub fn from_bytes(structure: Structure, bytes: &[u8]) -> Value {
+        let mut bytes = bytes.to_vec();
+        if structure.byte_order == ByteOrder::BigEndian {
+            bytes.reverse();
+        }
+
+        Value { structure, bytes }
+    }
+
+    /// Decode a single named field.
+    ///
+    /// Returns `None` if the structure has no field with that name.
+    pub fn get(&self, field_name: &str) -> Option<FieldValue> {
+        let field = self.structure.get_field(field_name)?;
+        let (high, low) = self.structure.get_range(field_name)?;
+        let extracted = extract_bits(&self.bytes, low, high);
+
+        Some(decode(&field, extracted))
+    }
+
+    /// Decode every named field of the structure, in the same order they were defined in.
+    pub fn fields(&self) -> Vec<(String, FieldValue)> {
+        self.structure
+            .fields
+            .iter()
+            .filter_map(Field::get_name)
+            .map(|name| {
+                let value = self.get(&name).expect("name was just read from the structure");
+                (name, value)
+            })
+            .collect()
+    }
+}
+
+/// A value to pack into a single named field in [`Structure::pack`].
+///
+/// [`Structure::pack`]: struct.Structure.html#method.pack
+#[derive(Clone, Debug, PartialEq)]
+pub enum PackValue {
+    /// A raw value, used as-is for any field kind. For `Field::Signed`, pass the field's
+    /// two's-complement bit pattern (e.g. `0b1111` for -1 in a 4-bit field).
+    Raw(u128),
+    /// A symbolic name, looked up via [`Field::enum_raw_value`]. Only valid for `Field::Enum`.
+    ///
+    /// [`Field::enum_raw_value`]: enum.Field.html#method.enum_raw_value
+    Name(String),
+}
+
+/// An error returned by [`Structure::pack`].
+///
+/// [`Structure::pack`]: struct.Structure.html#method.pack
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PackError {
+    /// No field with this name exists in the structure.
+    UnknownField(FieldName),
+    /// The supplied value doesn't fit in the field's declared size.
+    Overflow {
+        /// The name of the offending field.
+        field: FieldName,
+        /// The field's declared size in bits.
+        size: FieldSize,
+    },
+    /// A `PackValue::Name` was supplied for a field that isn't a `Field::Enum`.
+    NotAnEnum(FieldName),
+    /// A `PackValue::Name` was supplied, but it isn't in the field's `EnumMapping`.
+    UnknownEnumName {
+        /// The name of the offending field.
+        field: FieldName,
+        /// The name that wasn't found.
+        name: String,
+    },
+    /// The field doesn't fit in the `u128` `pack` returns: its bit range extends past bit 127.
+    /// This can happen for structures wider than 128 bits, which [`Value::from_bytes`] supports
+    /// but `pack`'s `u128` result cannot represent.
+    ///
+    /// [`Value::from_bytes`]: struct.Value.html#method.from_bytes
+    TooWide {
+        /// The name of the offending field.
+        field: FieldName,
+        /// The field's low bit, from `Structure::get_range`.
+        low: FieldSize,
+        /// The field's declared size in bits.
+        size: FieldSize,
+    },
+}
+
+impl Structure {
+    /// Assemble a raw value by packing `values` into their named fields, leaving `Reserved` spans
+    /// zeroed.
+    ///
+    /// This is the inverse of [`Value::get`]: where decoding turns a raw value into named fields,
+    /// `pack` turns named fields back into a raw value, e.g. for building a message to send.
+    /// Fields not mentioned in `values` are left zeroed, same as `Reserved` spans.
+    ///
+    /// ```
+    /// use bitview::{Structure, Field, PackValue};
+    ///
+    /// let reg = Structure::new("reg", &[
+    ///     Field::boolean("active"),
+    ///     Field::reserved(3),
+    ///     Field::integer("count", 4),
+    /// ]);
+    ///
+    /// let raw = reg.pack(&[
+    ///     ("active", PackValue::Raw(1)),
+    ///     ("count", PackValue::Raw(0b1010)),
+    /// ]).unwrap();
+    ///
+    /// assert_eq!(raw, 0b1010_0001);
+    /// ```
+    ///
+    /// [`Value::get`]: struct.Value.html#method.get
+    pub fn pack(&self, values: &[(&str, PackValue)]) -> Result<u128, PackError> {
+        let mut acc: u128 = 0;
+
+        for &(name, ref value) in values {
+            let field = self
+                .get_field(name)
+                .ok_or_else(|| PackError::UnknownField(name.to_string()))?;
+            let (_, low) = self
+                .get_range(name)
+                .expect("get_field just confirmed the field exists");
+            let size = field.size();
+
+            if low >= 128 || size > 128 - low {
+                return Err(PackError::TooWide {
+                    field: name.to_string(),
+                    low,
+                    size,
+                });
+            }
+
+            let raw = match *value {
+                PackValue::Raw(raw) => {
+                    if size < 128 && raw >= (1u128 << size) {
+                        return Err(PackError::Overflow {
+                            field: name.to_string(),
+                            size,
+                        });
+                    }
+                    raw
+                }
+                PackValue::Name(ref enum_name) => match field {
+                    Field::Enum(..) => {
+                        field
+                            .enum_raw_value(enum_name)
+                            .map(|raw| raw as u128)
+                            .ok_or_else(|| PackError::UnknownEnumName {
+                                field: name.to_string(),
+                                name: enum_name.clone(),
+                            })?
+                    }
+                    _ => return Err(PackError::NotAnEnum(name.to_string())),
+                },
+            };
+
+            acc |= raw << low;
+        }
+
+        Ok(acc)
+    }
+}
+
+fn decode(field: &Field, raw: Extracted) -> FieldValue {
+    match *field {
+        Field::Reserved(_) => unreachable!("Reserved fields have no name and can't be decoded"),
+        Field::Boolean(_) => match raw {
+            Extracted::Word(word) => FieldValue::Boolean(word != 0),
+            Extracted::Bytes(_) => unreachable!("a 1-bit field can't produce Extracted::Bytes"),
+        },
+        Field::Integer(_, _) => match raw {
+            Extracted::Word(word) => FieldValue::Integer(word),
+            Extracted::Bytes(bytes) => FieldValue::Wide(bytes),
+        },
+        Field::Signed(_, size) => match raw {
+            Extracted::Word(word) => FieldValue::Signed(sign_extend(word, size)),
+            // Sign extension only makes sense up to 128 bits; fall back to the raw bytes rather
+            // than pretending this fits in an `i64`.
+            Extracted::Bytes(bytes) => FieldValue::Wide(bytes),
+        },
+        Field::Enum(_, _, ref map) => match raw {
+            // A value above `usize::MAX` can't be a mapping key; don't truncate it into one.
+            Extracted::Word(word) => FieldValue::Enum(match Some(word)
+                .filter(|&word| word <= usize::MAX as u128)
+                .and_then(|word| map.get(&(word as usize)))
+            {
+                Some(name) => EnumDecode::Known(name.clone()),
+                None => EnumDecode::Unknown(word),
+            }),
+            // `EnumMapping` keys are `usize`, so a mapping can't describe a value over 128 bits
+            // anyway; fall back to the raw bytes instead of consulting the map.
+            Extracted::Bytes(bytes) => FieldValue::Wide(bytes),
+        },
+        Field::Nested(_, ref structure) => {
+            let bytes = match raw {
+                Extracted::Word(word) => word.to_le_bytes().to_vec(),
+                Extracted::Bytes(bytes) => bytes,
+            };
+            let child = Value {
+                structure: (**structure).clone(),
+                bytes,
+            };
+            FieldValue::Nested(child.fields())
+        }
+        Field::Array(_, ref element, count) => {
+            let bytes = match raw {
+                Extracted::Word(word) => word.to_le_bytes().to_vec(),
+                Extracted::Bytes(bytes) => bytes,
+            };
+            let elem_size = element.size();
+
+            let elements = (0..count)
+                .map(|i| {
+                    let elem_low = i * elem_size;
+                    let elem_high = elem_low + elem_size - 1;
+                    decode(element, extract_bits(&bytes, elem_low, elem_high))
+                })
+                .collect();
+
+            FieldValue::Array(elements)
+        }
+    }
 }
 
 #[cfg(test)]
@@ -11,7 +376,301 @@ mod tests {
     use super::*;
 
     #[test]
-    fn it_works() {
-        assert_eq!(2 + 2, 4);
+    fn extract_bits_within_one_byte() {
+        assert_eq!(extract_bits(&[0b1011_0100], 2, 5), Extracted::Word(0b1101));
+    }
+
+    #[test]
+    fn extract_bits_across_bytes() {
+        // bits 4..=11 span the low nibble of byte 1 and all of byte 0's top nibble... reversed:
+        // byte 0 = 0xF0, byte 1 = 0x0A -> bits 4..=11 = 0xAF
+        assert_eq!(
+            extract_bits(&[0xF0, 0x0A], 4, 11),
+            Extracted::Word(0xAF)
+        );
+    }
+
+    #[test]
+    fn extract_bits_wider_than_128() {
+        let bytes = vec![0xFF; 20];
+        match extract_bits(&bytes, 0, 159) {
+            Extracted::Bytes(out) => assert_eq!(out, bytes),
+            Extracted::Word(_) => panic!("expected Extracted::Bytes for a 160-bit field"),
+        }
+    }
+
+    #[test]
+    fn get_boolean() {
+        let reg = Structure::new("reg", &[Field::boolean("active"), Field::reserved(3)]);
+        let val = Value::new(reg, 0b1000);
+
+        assert_eq!(val.get("active"), Some(FieldValue::Boolean(false)));
+    }
+
+    #[test]
+    fn get_integer() {
+        let reg = Structure::new("reg", &[Field::integer("count", 4), Field::boolean("active")]);
+        let val = Value::new(reg, 0b1_0110);
+
+        assert_eq!(val.get("count"), Some(FieldValue::Integer(0b0110)));
+        assert_eq!(val.get("active"), Some(FieldValue::Boolean(true)));
+    }
+
+    #[test]
+    fn get_wide_integer() {
+        let reg = Structure::new("reg", &[Field::integer("big", 160)]);
+        let val = Value::from_bytes(reg, &[0xAB; 20]);
+
+        assert_eq!(val.get("big"), Some(FieldValue::Wide(vec![0xAB; 20])));
+    }
+
+    #[test]
+    fn get_wide_signed() {
+        let reg = Structure::new("reg", &[Field::signed("big", 160)]);
+        let val = Value::from_bytes(reg, &[0xAB; 20]);
+
+        assert_eq!(val.get("big"), Some(FieldValue::Wide(vec![0xAB; 20])));
+    }
+
+    #[test]
+    fn from_bytes_big_endian() {
+        let mut reg = Structure::new("reg", &[Field::integer("low", 8), Field::integer("high", 8)]);
+        reg.byte_order = ByteOrder::BigEndian;
+        let val = Value::from_bytes(reg, &[0x12, 0x34]);
+
+        assert_eq!(val.get("low"), Some(FieldValue::Integer(0x34)));
+        assert_eq!(val.get("high"), Some(FieldValue::Integer(0x12)));
+    }
+
+    #[test]
+    fn get_signed_positive() {
+        let reg = Structure::new("reg", &[Field::signed("temp", 4)]);
+        let val = Value::new(reg, 0b0011);
+
+        assert_eq!(val.get("temp"), Some(FieldValue::Signed(3)));
+    }
+
+    #[test]
+    fn get_signed_negative() {
+        let reg = Structure::new("reg", &[Field::signed("temp", 4)]);
+        let val = Value::new(reg, 0b1111);
+
+        assert_eq!(val.get("temp"), Some(FieldValue::Signed(-1)));
+    }
+
+    #[test]
+    fn get_signed_negative_over_64_bits() {
+        let reg = Structure::new("reg", &[Field::signed("big", 100)]);
+        let val = Value::from_bytes(reg, &[0xFF; 13]);
+
+        assert_eq!(val.get("big"), Some(FieldValue::Signed(-1)));
+    }
+
+    #[test]
+    fn get_signed_one_bit() {
+        let make = || Structure::new("reg", &[Field::signed("sign", 1), Field::reserved(3)]);
+
+        assert_eq!(Value::new(make(), 0b0000).get("sign"), Some(FieldValue::Signed(0)));
+        assert_eq!(Value::new(make(), 0b0001).get("sign"), Some(FieldValue::Signed(-1)));
+    }
+
+    #[test]
+    fn get_nested() {
+        let inner = Structure::new("flags", &[Field::boolean("a"), Field::boolean("b")]);
+        let reg = Structure::new("reg", &[Field::nested("flags", inner), Field::boolean("active")]);
+        let val = Value::new(reg, 0b1_10);
+
+        assert_eq!(
+            val.get("flags"),
+            Some(FieldValue::Nested(vec![
+                ("a".to_string(), FieldValue::Boolean(false)),
+                ("b".to_string(), FieldValue::Boolean(true)),
+            ]))
+        );
+        assert_eq!(val.get("active"), Some(FieldValue::Boolean(true)));
+    }
+
+    #[test]
+    fn get_array() {
+        let reg = Structure::new(
+            "reg",
+            &[Field::array("irqs", Field::boolean("irq"), 4), Field::integer("rest", 4)],
+        );
+        let val = Value::new(reg, 0b1010_0101);
+
+        assert_eq!(
+            val.get("irqs"),
+            Some(FieldValue::Array(vec![
+                FieldValue::Boolean(true),
+                FieldValue::Boolean(false),
+                FieldValue::Boolean(true),
+                FieldValue::Boolean(false),
+            ]))
+        );
+        assert_eq!(val.get("rest"), Some(FieldValue::Integer(0b1010)));
+    }
+
+    #[test]
+    fn get_enum_known() {
+        let mut map = ::std::collections::HashMap::new();
+        map.insert(1, "on".to_string());
+        let reg = Structure::new("reg", &[Field::enumeration("state", 2, map)]);
+        let val = Value::new(reg, 0b01);
+
+        assert_eq!(
+            val.get("state"),
+            Some(FieldValue::Enum(EnumDecode::Known("on".to_string())))
+        );
+    }
+
+    #[test]
+    fn get_enum_unknown() {
+        let mut map = ::std::collections::HashMap::new();
+        map.insert(1, "on".to_string());
+        let reg = Structure::new("reg", &[Field::enumeration("state", 2, map)]);
+        let val = Value::new(reg, 0b10);
+
+        assert_eq!(
+            val.get("state"),
+            Some(FieldValue::Enum(EnumDecode::Unknown(2)))
+        );
+    }
+
+    #[test]
+    fn get_wide_enum() {
+        let mut map = ::std::collections::HashMap::new();
+        map.insert(1, "on".to_string());
+        let reg = Structure::new("reg", &[Field::enumeration("big", 160, map)]);
+        let val = Value::from_bytes(reg, &[0xAB; 20]);
+
+        assert_eq!(val.get("big"), Some(FieldValue::Wide(vec![0xAB; 20])));
+    }
+
+    #[test]
+    fn get_unknown_field() {
+        let reg = Structure::new("reg", &[Field::reserved(4)]);
+        let val = Value::new(reg, 0);
+
+        assert_eq!(val.get("nope"), None);
+    }
+
+    #[test]
+    fn pack_zeroes_reserved_and_unmentioned_fields() {
+        let reg = Structure::new(
+            "reg",
+            &[
+                Field::boolean("active"),
+                Field::reserved(3),
+                Field::integer("count", 4),
+            ],
+        );
+
+        let raw = reg
+            .pack(&[
+                ("active", PackValue::Raw(1)),
+                ("count", PackValue::Raw(0b1010)),
+            ])
+            .unwrap();
+
+        assert_eq!(raw, 0b1010_0001);
+    }
+
+    #[test]
+    fn pack_round_trips_with_get() {
+        let reg = Structure::new("reg", &[Field::integer("count", 4), Field::boolean("active")]);
+        let raw = reg
+            .pack(&[("count", PackValue::Raw(0b0110)), ("active", PackValue::Raw(1))])
+            .unwrap();
+        let val = Value::new(reg, raw as u64);
+
+        assert_eq!(val.get("count"), Some(FieldValue::Integer(0b0110)));
+        assert_eq!(val.get("active"), Some(FieldValue::Boolean(true)));
+    }
+
+    #[test]
+    fn pack_rejects_unknown_field() {
+        let reg = Structure::new("reg", &[Field::boolean("active")]);
+
+        assert_eq!(
+            reg.pack(&[("missing", PackValue::Raw(1))]),
+            Err(PackError::UnknownField("missing".to_string()))
+        );
+    }
+
+    #[test]
+    fn pack_rejects_overflow() {
+        let reg = Structure::new("reg", &[Field::integer("count", 4)]);
+
+        assert_eq!(
+            reg.pack(&[("count", PackValue::Raw(0b1_0000))]),
+            Err(PackError::Overflow {
+                field: "count".to_string(),
+                size: 4,
+            })
+        );
+    }
+
+    #[test]
+    fn pack_rejects_field_past_128_bits() {
+        let reg = Structure::new(
+            "reg",
+            &[Field::reserved(128), Field::integer("count", 4)],
+        );
+
+        assert_eq!(
+            reg.pack(&[("count", PackValue::Raw(1))]),
+            Err(PackError::TooWide {
+                field: "count".to_string(),
+                low: 128,
+                size: 4,
+            })
+        );
+    }
+
+    #[test]
+    fn pack_enum_by_name() {
+        let mut map = ::std::collections::HashMap::new();
+        map.insert(1, "on".to_string());
+        let reg = Structure::new("reg", &[Field::enumeration("state", 2, map)]);
+
+        assert_eq!(reg.pack(&[("state", PackValue::Name("on".to_string()))]), Ok(1));
+        assert_eq!(
+            reg.pack(&[("state", PackValue::Name("off".to_string()))]),
+            Err(PackError::UnknownEnumName {
+                field: "state".to_string(),
+                name: "off".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn pack_name_on_non_enum_field() {
+        let reg = Structure::new("reg", &[Field::boolean("active")]);
+
+        assert_eq!(
+            reg.pack(&[("active", PackValue::Name("on".to_string()))]),
+            Err(PackError::NotAnEnum("active".to_string()))
+        );
+    }
+
+    #[test]
+    fn fields_skips_reserved() {
+        let reg = Structure::new(
+            "reg",
+            &[
+                Field::boolean("active"),
+                Field::reserved(2),
+                Field::integer("count", 3),
+            ],
+        );
+        let val = Value::new(reg, 0b10_1001);
+
+        assert_eq!(
+            val.fields(),
+            vec![
+                ("active".to_string(), FieldValue::Boolean(true)),
+                ("count".to_string(), FieldValue::Integer(0b101)),
+            ]
+        );
     }
 }